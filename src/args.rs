@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use crate::chunk_type::ChunkType;
+use crate::metadata::MetaField;
 use structopt::StructOpt;
 
 #[derive(StructOpt, Debug)]
@@ -18,13 +19,31 @@ pub(crate) enum Command {
     Encode {
         #[structopt(short = "t", parse(try_from_str))]
         chunk_type: ChunkType,
+        /// The message to encode; omit when using --file
         #[structopt(name = "MESSAGE")]
-        message: String,
+        message: Option<String>,
+        /// Read the chunk data from this file instead of MESSAGE, for
+        /// embedding arbitrary binary payloads
+        #[structopt(long, parse(from_os_str), conflicts_with = "MESSAGE")]
+        file: Option<PathBuf>,
+        /// Encrypt the message with this passphrase before storing it
+        #[structopt(long)]
+        passphrase: Option<String>,
+        /// Split the payload across multiple ordered chunks of this size
+        /// instead of one, for payloads too large for a single chunk
+        #[structopt(long)]
+        max_chunk_bytes: Option<usize>,
     },
     /// Decode the supplied chunk type as a string
     Decode {
         #[structopt(short = "t", parse(try_from_str))]
         chunk_type: ChunkType,
+        /// Write the raw chunk data to this file instead of printing it as text
+        #[structopt(long, parse(from_os_str))]
+        out: Option<PathBuf>,
+        /// Decrypt the chunk data with this passphrase before printing it
+        #[structopt(long)]
+        passphrase: Option<String>,
     },
     /// Remove the supplied chunk type
     Remove {
@@ -33,4 +52,21 @@ pub(crate) enum Command {
     },
     /// Print all chunks
     PrintAll,
+    /// Set a structured metadata field (author, timestamp, comment or mime-type)
+    SetMeta {
+        #[structopt(parse(try_from_str))]
+        key: MetaField,
+        value: String,
+    },
+    /// Read a single structured metadata field
+    GetMeta {
+        #[structopt(parse(try_from_str))]
+        key: MetaField,
+    },
+    /// Print every structured metadata field
+    PrintMeta,
+    /// Report each chunk's stored vs. computed CRC
+    Verify,
+    /// Rewrite every chunk with a freshly computed CRC
+    Repair,
 }
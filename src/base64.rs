@@ -0,0 +1,70 @@
+const ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = *chunk.get(1).unwrap_or(&0);
+        let b2 = *chunk.get(2).unwrap_or(&0);
+
+        let c0 = b0 >> 2;
+        let c1 = ((b0 & 0b0000_0011) << 4) | (b1 >> 4);
+        let c2 = ((b1 & 0b0000_1111) << 2) | (b2 >> 6);
+        let c3 = b2 & 0b0011_1111;
+
+        out.push(ALPHABET[c0 as usize] as char);
+        out.push(ALPHABET[c1 as usize] as char);
+        out.push(if chunk.len() > 1 {
+            ALPHABET[c2 as usize] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            ALPHABET[c3 as usize] as char
+        } else {
+            '='
+        });
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_empty() {
+        assert_eq!(encode(b""), "");
+    }
+
+    #[test]
+    fn test_encode_no_padding() {
+        assert_eq!(encode(b"Man"), "TWFu");
+    }
+
+    #[test]
+    fn test_encode_one_padding_byte() {
+        assert_eq!(encode(b"Ma"), "TWE=");
+    }
+
+    #[test]
+    fn test_encode_two_padding_bytes() {
+        assert_eq!(encode(b"M"), "TQ==");
+    }
+
+    #[test]
+    fn test_encode_sentence() {
+        assert_eq!(
+            encode(b"pleasure."),
+            "cGxlYXN1cmUu"
+        );
+    }
+
+    #[test]
+    fn test_encode_binary_data() {
+        assert_eq!(encode(&[0xff, 0x00, 0x10]), "/wAQ");
+    }
+}
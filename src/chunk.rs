@@ -1,9 +1,23 @@
-use std::{fmt::Display, str::Utf8Error};
+use std::{
+    fmt::Display,
+    io::{ErrorKind, Read},
+    str::Utf8Error,
+};
 
 use crc::{Algorithm, Crc};
 
 use crate::chunk_type::{self, ChunkType};
 
+/// The largest chunk data length accepted from an untrusted stream, matching
+/// the PNG spec's own limit on a chunk's length field. [`Chunk::read_fields`]
+/// rejects a 4-byte length prefix above this bound before reading any data.
+const MAX_CHUNK_DATA_LEN: usize = (1 << 31) - 1;
+
+/// Size of the buffer used to read chunk data off the stream in bounded
+/// steps, so a large `length` only ever causes small, incremental
+/// allocations instead of one allocation sized off an unverified field.
+const READ_BUF_LEN: usize = 64 * 1024;
+
 #[derive(Clone)]
 pub(crate) struct Chunk {
     chunk_type: ChunkType,
@@ -19,11 +33,11 @@ impl Into<(ChunkType, Chunk)> for Chunk {
 
 impl Chunk {
     pub fn crc_checksum(chunk_type: &ChunkType, data: &Vec<u8>) -> u32 {
-        let mut all_data: Vec<u8> = chunk_type.bytes().to_vec();
-        all_data.extend(data.clone());
-
         let crc: Crc<u32> = Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
-        crc.checksum(all_data.as_slice())
+        let mut digest = crc.digest();
+        digest.update(&chunk_type.bytes());
+        digest.update(data);
+        digest.finalize()
     }
 
     pub fn new(chunk_type: ChunkType, data: Vec<u8>) -> Self {
@@ -44,6 +58,10 @@ impl Chunk {
         &self.chunk_type
     }
 
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
     pub fn data_as_string(&self) -> Result<String, Utf8Error> {
         let s = std::str::from_utf8(&self.data)?;
         Ok(s.to_owned())
@@ -60,6 +78,118 @@ impl Chunk {
 
         bytes
     }
+
+    /// Reads one chunk from `reader` using `read_exact` for each field
+    /// (length, type, data, CRC) and verifying the checksum as the chunk is
+    /// assembled, rather than requiring the whole file to be buffered up
+    /// front. A clean end-of-stream while reading the length field is
+    /// reported as [`ChunkReadError::Eof`] so a lazy chunk iterator can tell
+    /// "no more chunks" apart from "corrupt chunk".
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Chunk, ChunkReadError> {
+        let (chunk_type, data, crc, computed_crc) = Self::read_fields(reader)?;
+
+        if computed_crc != crc {
+            return Err(ChunkReadError::Invalid);
+        }
+
+        Ok(Chunk::from_raw_parts(chunk_type, data, crc))
+    }
+
+    /// Like [`Chunk::read_from`], but loads the chunk even when its stored
+    /// CRC doesn't match the data, keeping the stored value verbatim so
+    /// callers (`Verify`, `Repair`) can report or fix the mismatch. Still
+    /// fails on structural corruption (an unreadable length/type/data/CRC
+    /// field), since there's nothing to salvage there.
+    pub fn read_from_lenient<R: Read>(reader: &mut R) -> Result<Chunk, ChunkReadError> {
+        let (chunk_type, data, crc, _) = Self::read_fields(reader)?;
+        Ok(Chunk::from_raw_parts(chunk_type, data, crc))
+    }
+
+    /// Reads the length, type, data and CRC fields of one chunk off
+    /// `reader`, without validating the CRC against the stored value (the
+    /// computed CRC is handed back alongside it so callers can decide).
+    /// `length` is rejected outright if it exceeds [`MAX_CHUNK_DATA_LEN`],
+    /// and the data itself is read in [`READ_BUF_LEN`]-sized steps rather
+    /// than allocated in one shot, so a corrupt or hostile length prefix
+    /// can't force a multi-gigabyte allocation before `read_exact` has had
+    /// a chance to fail on a stream that doesn't actually hold that much
+    /// data. The CRC is accumulated incrementally over chunk type and data
+    /// as they're read, rather than copied into a second buffer afterwards.
+    /// A clean end-of-stream while reading the length field is reported as
+    /// [`ChunkReadError::Eof`].
+    fn read_fields<R: Read>(
+        reader: &mut R,
+    ) -> Result<(ChunkType, Vec<u8>, u32, u32), ChunkReadError> {
+        let mut length_bytes = [0u8; 4];
+        match reader.read_exact(&mut length_bytes[..1]) {
+            Ok(()) => {}
+            Err(ref e) if e.kind() == ErrorKind::UnexpectedEof => return Err(ChunkReadError::Eof),
+            Err(_) => return Err(ChunkReadError::Invalid),
+        }
+        reader
+            .read_exact(&mut length_bytes[1..])
+            .map_err(|_| ChunkReadError::Invalid)?;
+        let length = u32::from_be_bytes(length_bytes) as usize;
+        if length > MAX_CHUNK_DATA_LEN {
+            return Err(ChunkReadError::Invalid);
+        }
+
+        let mut chunk_type_bytes = [0u8; 4];
+        reader
+            .read_exact(&mut chunk_type_bytes)
+            .map_err(|_| ChunkReadError::Invalid)?;
+        let chunk_type = ChunkType::try_from(chunk_type_bytes).map_err(|_| ChunkReadError::Invalid)?;
+
+        let crc_algorithm: Crc<u32> = Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+        let mut digest = crc_algorithm.digest();
+        digest.update(&chunk_type_bytes);
+
+        let mut data = Vec::with_capacity(length.min(READ_BUF_LEN));
+        let mut remaining = length;
+        let mut buf = [0u8; READ_BUF_LEN];
+        while remaining > 0 {
+            let step = remaining.min(READ_BUF_LEN);
+            reader
+                .read_exact(&mut buf[..step])
+                .map_err(|_| ChunkReadError::Invalid)?;
+            digest.update(&buf[..step]);
+            data.extend_from_slice(&buf[..step]);
+            remaining -= step;
+        }
+        let computed_crc = digest.finalize();
+
+        let mut crc_bytes = [0u8; 4];
+        reader
+            .read_exact(&mut crc_bytes)
+            .map_err(|_| ChunkReadError::Invalid)?;
+        let crc = u32::from_be_bytes(crc_bytes);
+
+        Ok((chunk_type, data, crc, computed_crc))
+    }
+
+    /// Builds a chunk from its raw parts without recomputing the CRC,
+    /// preserving a possibly-wrong stored value. Used by the stream parsers
+    /// ([`Chunk::read_from`] and [`Chunk::read_from_lenient`]); prefer
+    /// [`Chunk::new`] everywhere else.
+    fn from_raw_parts(chunk_type: ChunkType, data: Vec<u8>, crc: u32) -> Chunk {
+        Chunk {
+            chunk_type,
+            data,
+            crc,
+        }
+    }
+
+    /// The CRC freshly computed from the chunk's current type and data,
+    /// regardless of what its stored [`Chunk::crc`] says.
+    pub fn computed_crc(&self) -> u32 {
+        Chunk::crc_checksum(&self.chunk_type, &self.data)
+    }
+
+    /// Whether the chunk's stored CRC matches one freshly computed from its
+    /// type and data.
+    pub fn is_crc_valid(&self) -> bool {
+        self.computed_crc() == self.crc
+    }
 }
 
 impl Display for Chunk {
@@ -69,6 +199,15 @@ impl Display for Chunk {
     }
 }
 
+/// Outcome of attempting to read one chunk header off a stream: either the
+/// stream is cleanly exhausted (no more chunks), or a chunk was present but
+/// failed to parse/checksum.
+#[derive(Debug)]
+pub(crate) enum ChunkReadError {
+    Eof,
+    Invalid,
+}
+
 impl TryFrom<&[u8]> for Chunk {
     type Error = ();
 
@@ -229,6 +368,95 @@ mod tests {
         assert!(chunk.is_err());
     }
 
+    #[test]
+    fn test_chunk_read_from() {
+        let data_length: u32 = 42;
+        let chunk_type = "RuSt".as_bytes();
+        let message_bytes = "This is where your secret message will be!".as_bytes();
+        let crc: u32 = 2882656334;
+
+        let chunk_data: Vec<u8> = data_length
+            .to_be_bytes()
+            .iter()
+            .chain(chunk_type.iter())
+            .chain(message_bytes.iter())
+            .chain(crc.to_be_bytes().iter())
+            .copied()
+            .collect();
+
+        let chunk = Chunk::read_from(&mut chunk_data.as_slice()).unwrap();
+
+        assert_eq!(chunk.length(), 42);
+        assert_eq!(chunk.chunk_type().to_string(), String::from("RuSt"));
+        assert_eq!(chunk.crc(), 2882656334);
+    }
+
+    #[test]
+    fn test_chunk_read_from_invalid_crc() {
+        let data_length: u32 = 42;
+        let chunk_type = "RuSt".as_bytes();
+        let message_bytes = "This is where your secret message will be!".as_bytes();
+        let crc: u32 = 2882656333;
+
+        let chunk_data: Vec<u8> = data_length
+            .to_be_bytes()
+            .iter()
+            .chain(chunk_type.iter())
+            .chain(message_bytes.iter())
+            .chain(crc.to_be_bytes().iter())
+            .copied()
+            .collect();
+
+        let result = Chunk::read_from(&mut chunk_data.as_slice());
+
+        assert!(matches!(result, Err(ChunkReadError::Invalid)));
+    }
+
+    #[test]
+    fn test_chunk_read_from_eof() {
+        let chunk_data: Vec<u8> = Vec::new();
+
+        let result = Chunk::read_from(&mut chunk_data.as_slice());
+
+        assert!(matches!(result, Err(ChunkReadError::Eof)));
+    }
+
+    #[test]
+    fn test_chunk_read_from_rejects_oversized_length_without_allocating() {
+        // A length prefix claiming far more data than the (short) stream
+        // actually holds must be rejected outright, not turned into a
+        // multi-gigabyte allocation attempt.
+        let mut chunk_data = u32::MAX.to_be_bytes().to_vec();
+        chunk_data.extend_from_slice(b"RuSt");
+
+        let result = Chunk::read_from(&mut chunk_data.as_slice());
+
+        assert!(matches!(result, Err(ChunkReadError::Invalid)));
+    }
+
+    #[test]
+    fn test_chunk_read_from_lenient_keeps_bad_crc() {
+        let data_length: u32 = 42;
+        let chunk_type = "RuSt".as_bytes();
+        let message_bytes = "This is where your secret message will be!".as_bytes();
+        let crc: u32 = 2882656333;
+
+        let chunk_data: Vec<u8> = data_length
+            .to_be_bytes()
+            .iter()
+            .chain(chunk_type.iter())
+            .chain(message_bytes.iter())
+            .chain(crc.to_be_bytes().iter())
+            .copied()
+            .collect();
+
+        let chunk = Chunk::read_from_lenient(&mut chunk_data.as_slice()).unwrap();
+
+        assert_eq!(chunk.crc(), crc);
+        assert!(!chunk.is_crc_valid());
+        assert_eq!(chunk.computed_crc(), 2882656334);
+    }
+
     #[test]
     pub fn test_chunk_trait_impls() {
         let data_length: u32 = 42;
@@ -0,0 +1,234 @@
+use std::io::Read;
+
+const KEY_LEN: usize = 32;
+pub const SALT_LEN: usize = 8;
+
+/// Prefixed onto every payload written by [`encrypt`], so callers can tell
+/// "this chunk is encrypted" from "this chunk merely isn't valid UTF-8"
+/// instead of guessing from whether UTF-8 decoding happens to fail.
+const MAGIC: [u8; 4] = *b"pgE1";
+
+pub fn encrypt(passphrase: &str, plaintext: &[u8]) -> Vec<u8> {
+    let salt = random_salt();
+    let key = derive_key(passphrase, &salt);
+
+    let mut out = Vec::with_capacity(MAGIC.len() + SALT_LEN + plaintext.len());
+    out.extend_from_slice(&MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend(apply_keystream(&key, plaintext));
+    out
+}
+
+pub fn decrypt(passphrase: &str, data: &[u8]) -> Result<Vec<u8>, ()> {
+    let data = data.strip_prefix(&MAGIC).ok_or(())?;
+    if data.len() < SALT_LEN {
+        return Err(());
+    }
+    let (salt, ciphertext) = data.split_at(SALT_LEN);
+    let key = derive_key(passphrase, salt);
+    Ok(apply_keystream(&key, ciphertext))
+}
+
+/// Whether `data` carries the magic prefix written by [`encrypt`]. Used to
+/// warn about (or refuse to print) an encrypted chunk's data instead of
+/// relying on it coincidentally failing UTF-8 validation.
+pub fn looks_encrypted(data: &[u8]) -> bool {
+    data.starts_with(&MAGIC)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> [u8; KEY_LEN] {
+    let mut input = Vec::with_capacity(passphrase.len() + salt.len());
+    input.extend_from_slice(passphrase.as_bytes());
+    input.extend_from_slice(salt);
+    sha256(&input)
+}
+
+fn apply_keystream(key: &[u8; KEY_LEN], data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+
+    for (counter, block) in data.chunks(KEY_LEN).enumerate() {
+        let mut block_input = Vec::with_capacity(KEY_LEN + 8);
+        block_input.extend_from_slice(key);
+        block_input.extend_from_slice(&(counter as u64).to_be_bytes());
+        let keystream = sha256(&block_input);
+
+        for (byte, stream_byte) in block.iter().zip(keystream.iter()) {
+            out.push(byte ^ stream_byte);
+        }
+    }
+
+    out
+}
+
+fn random_salt() -> [u8; SALT_LEN] {
+    let mut salt = [0u8; SALT_LEN];
+
+    let read_urandom = std::fs::File::open("/dev/urandom").and_then(|mut f| f.read_exact(&mut salt));
+
+    if read_urandom.is_err() {
+        // No OS entropy source available: mix whatever non-deterministic
+        // state is cheaply at hand instead of failing outright.
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let marker = &salt as *const _ as u64;
+
+        let mut seed = nanos.to_be_bytes().to_vec();
+        seed.extend_from_slice(&marker.to_be_bytes());
+        let mixed = sha256(&seed);
+        salt.copy_from_slice(&mixed[..SALT_LEN]);
+    }
+
+    salt
+}
+
+const H0: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+pub fn sha256(input: &[u8]) -> [u8; 32] {
+    let mut h = H0;
+
+    for block in pad(input).chunks(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in w.iter_mut().enumerate().take(16) {
+            *word = u32::from_be_bytes(block[i * 4..i * 4 + 4].try_into().unwrap());
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let (mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh) =
+            (h[0], h[1], h[2], h[3], h[4], h[5], h[6], h[7]);
+
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ ((!e) & g);
+            let temp1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(maj);
+
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+
+        h[0] = h[0].wrapping_add(a);
+        h[1] = h[1].wrapping_add(b);
+        h[2] = h[2].wrapping_add(c);
+        h[3] = h[3].wrapping_add(d);
+        h[4] = h[4].wrapping_add(e);
+        h[5] = h[5].wrapping_add(f);
+        h[6] = h[6].wrapping_add(g);
+        h[7] = h[7].wrapping_add(hh);
+    }
+
+    let mut out = [0u8; 32];
+    for (i, word) in h.iter().enumerate() {
+        out[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+fn pad(input: &[u8]) -> Vec<u8> {
+    let mut data = input.to_vec();
+    let bit_len = (input.len() as u64) * 8;
+
+    data.push(0x80);
+    while data.len() % 64 != 56 {
+        data.push(0);
+    }
+    data.extend_from_slice(&bit_len.to_be_bytes());
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_sha256_empty() {
+        assert_eq!(
+            hex(&sha256(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_sha256_abc() {
+        assert_eq!(
+            hex(&sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_sha256_long_input_spans_multiple_blocks() {
+        let input = "a".repeat(1000);
+        assert_eq!(
+            hex(&sha256(input.as_bytes())),
+            "41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3"
+        );
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let plaintext = b"This is where your secret message will be!";
+        let encrypted = encrypt("correct horse battery staple", plaintext);
+        let decrypted = decrypt("correct horse battery staple", &encrypted).unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn test_wrong_passphrase_does_not_roundtrip() {
+        let plaintext = b"This is where your secret message will be!";
+        let encrypted = encrypt("correct horse battery staple", plaintext);
+        let decrypted = decrypt("wrong passphrase", &encrypted).unwrap();
+        assert_ne!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn test_decrypt_rejects_data_shorter_than_salt() {
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(&[0u8; SALT_LEN - 1]);
+        assert!(decrypt("passphrase", &data).is_err());
+    }
+
+    #[test]
+    fn test_looks_encrypted() {
+        let encrypted = encrypt("correct horse battery staple", b"secret");
+        assert!(looks_encrypted(&encrypted));
+        assert!(!looks_encrypted(b"plain, unencrypted chunk data"));
+    }
+}
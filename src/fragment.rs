@@ -0,0 +1,185 @@
+use std::fmt::Display;
+
+/// Each fragment's data is prefixed with a 4-byte total-part count and a
+/// 4-byte zero-based part index, both big-endian.
+const HEADER_LEN: usize = 8;
+
+#[derive(Debug)]
+pub enum FragmentError {
+    ChunkTooSmall,
+    Truncated,
+    Empty,
+    InconsistentTotal,
+    Incomplete { expected: u32, found: usize },
+    Gap { expected_index: u32, found_index: u32 },
+}
+
+impl Display for FragmentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FragmentError::ChunkTooSmall => {
+                write!(f, "--max-chunk-bytes is too small to fit the fragment header")
+            }
+            FragmentError::Truncated => write!(f, "a fragment is smaller than the fragment header"),
+            FragmentError::Empty => write!(f, "no fragments were supplied to reassemble"),
+            FragmentError::InconsistentTotal => {
+                write!(f, "fragments disagree on the total part count")
+            }
+            FragmentError::Incomplete { expected, found } => {
+                write!(f, "expected {} parts but found {}", expected, found)
+            }
+            FragmentError::Gap {
+                expected_index,
+                found_index,
+            } => write!(
+                f,
+                "missing part {} (found part {} instead)",
+                expected_index, found_index
+            ),
+        }
+    }
+}
+
+/// Splits `data` into a series of fragments no larger than `max_chunk_bytes`,
+/// each prefixed with the total-part/part-index header, so the whole can be
+/// reassembled by [`reassemble`]. When `data` already fits within
+/// `max_chunk_bytes`, it's returned unsplit and without a header, so a
+/// single-part payload round-trips as a plain chunk.
+pub fn split(data: &[u8], max_chunk_bytes: usize) -> Result<Vec<Vec<u8>>, FragmentError> {
+    if data.len() <= max_chunk_bytes {
+        return Ok(vec![data.to_vec()]);
+    }
+
+    if max_chunk_bytes <= HEADER_LEN {
+        return Err(FragmentError::ChunkTooSmall);
+    }
+
+    let fragment_len = max_chunk_bytes - HEADER_LEN;
+    let total = data.chunks(fragment_len).count() as u32;
+
+    let parts = data
+        .chunks(fragment_len)
+        .enumerate()
+        .map(|(index, fragment)| {
+            let mut part = Vec::with_capacity(HEADER_LEN + fragment.len());
+            part.extend(total.to_be_bytes());
+            part.extend((index as u32).to_be_bytes());
+            part.extend(fragment);
+            part
+        })
+        .collect();
+
+    Ok(parts)
+}
+
+/// Reassembles the fragments produced by [`split`], verifying that they all
+/// agree on the total part count, that none are missing, and that their
+/// indices are contiguous from 0.
+pub fn reassemble(fragments: Vec<&[u8]>) -> Result<Vec<u8>, FragmentError> {
+    let mut parts: Vec<(u32, u32, &[u8])> = fragments
+        .into_iter()
+        .map(|data| {
+            if data.len() < HEADER_LEN {
+                return Err(FragmentError::Truncated);
+            }
+            let total = u32::from_be_bytes(data[0..4].try_into().unwrap());
+            let index = u32::from_be_bytes(data[4..8].try_into().unwrap());
+            Ok((total, index, &data[HEADER_LEN..]))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if parts.is_empty() {
+        return Err(FragmentError::Empty);
+    }
+
+    parts.sort_by_key(|(_, index, _)| *index);
+
+    let total = parts[0].0;
+    if parts.iter().any(|(part_total, _, _)| *part_total != total) {
+        return Err(FragmentError::InconsistentTotal);
+    }
+    if parts.len() as u32 != total {
+        return Err(FragmentError::Incomplete {
+            expected: total,
+            found: parts.len(),
+        });
+    }
+    for (expected_index, (_, index, _)) in parts.iter().enumerate() {
+        if *index != expected_index as u32 {
+            return Err(FragmentError::Gap {
+                expected_index: expected_index as u32,
+                found_index: *index,
+            });
+        }
+    }
+
+    Ok(parts.into_iter().flat_map(|(_, _, data)| data.to_vec()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_fits_in_one_chunk() {
+        let data = b"hello";
+        assert_eq!(split(data, 100).unwrap(), vec![data.to_vec()]);
+    }
+
+    #[test]
+    fn test_split_and_reassemble_roundtrip() {
+        let data: Vec<u8> = (0..100u16).map(|n| (n % 256) as u8).collect();
+        let parts = split(&data, 16).unwrap();
+        assert!(parts.len() > 1);
+
+        let reassembled = reassemble(parts.iter().map(|p| p.as_slice()).collect()).unwrap();
+        assert_eq!(reassembled, data);
+    }
+
+    #[test]
+    fn test_reassemble_out_of_order() {
+        let data: Vec<u8> = (0..50u8).collect();
+        let mut parts = split(&data, 16).unwrap();
+        parts.reverse();
+
+        let reassembled = reassemble(parts.iter().map(|p| p.as_slice()).collect()).unwrap();
+        assert_eq!(reassembled, data);
+    }
+
+    #[test]
+    fn test_reassemble_missing_part() {
+        let data: Vec<u8> = (0..50u8).collect();
+        let mut parts = split(&data, 16).unwrap();
+        parts.remove(1);
+
+        let err = reassemble(parts.iter().map(|p| p.as_slice()).collect()).unwrap_err();
+        assert!(matches!(err, FragmentError::Incomplete { .. }));
+    }
+
+    #[test]
+    fn test_reassemble_gap() {
+        let data: Vec<u8> = (0..64u8).collect();
+        let mut parts = split(&data, 16).unwrap();
+        parts.remove(1);
+        // Re-using one of the remaining parts keeps the count right but
+        // leaves a gap at index 1.
+        let duplicate = parts[0].clone();
+        parts.push(duplicate);
+
+        let err = reassemble(parts.iter().map(|p| p.as_slice()).collect()).unwrap_err();
+        assert!(matches!(err, FragmentError::Gap { .. }));
+    }
+
+    #[test]
+    fn test_split_rejects_chunk_too_small_for_header() {
+        let data = vec![0u8; 100];
+        let err = split(&data, 4).unwrap_err();
+        assert!(matches!(err, FragmentError::ChunkTooSmall));
+    }
+
+    #[test]
+    fn test_reassemble_rejects_empty_input() {
+        let err = reassemble(vec![]).unwrap_err();
+        assert!(matches!(err, FragmentError::Empty));
+    }
+}
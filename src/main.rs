@@ -1,6 +1,10 @@
 mod args;
+mod base64;
 mod chunk;
 mod chunk_type;
+mod crypto;
+mod fragment;
+mod metadata;
 mod png;
 
 use args::Args;
@@ -17,59 +21,323 @@ use crate::png::Png;
 pub type Error = Box<dyn std::error::Error>;
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Rewrites `file` in place with `png`'s current bytes, then truncates to
+/// the new length. A rewrite that's shorter than what was previously on
+/// disk (removing a chunk, or replacing one with something smaller) would
+/// otherwise leave the old bytes physically present past the new `IEND` —
+/// readable on disk even though no chunk references them anymore.
+fn rewrite_png(file: &File, png: &Png) -> Result<()> {
+    let bytes = png.as_bytes();
+
+    let mut writer = BufWriter::new(file);
+    writer.seek(SeekFrom::Start(0))?;
+    writer.write_all(&bytes)?;
+    writer.flush()?;
+
+    file.set_len(bytes.len() as u64)?;
+    Ok(())
+}
+
+/// Renders a chunk's (possibly reassembled) data for `PrintAll`: encrypted
+/// data gets a label, valid UTF-8 prints as text, anything else renders as
+/// base64 so binary payloads stay inspectable on a terminal.
+fn render_chunk_message(data: &[u8]) -> String {
+    if crypto::looks_encrypted(data) {
+        "<encrypted, supply --passphrase via decode to read>".to_string()
+    } else {
+        std::str::from_utf8(data)
+            .map(str::to_string)
+            .unwrap_or_else(|_| base64::encode(data))
+    }
+}
+
 fn main() -> Result<()> {
     let args = Args::from_args();
     let file = File::options().read(true).write(true).open(args.file)?;
 
-    let mut reader = BufReader::new(&file);
-    let mut png =
-        Png::try_from(&mut reader).map_err(|_| anyhow::format_err!("Invalid PNG file supplied"))?;
-
     match args.command {
         args::Command::Encode {
             chunk_type,
             message,
+            file: input_file,
+            passphrase,
+            max_chunk_bytes,
         } => {
-            let chunk = Chunk::new(chunk_type, message.as_bytes().into());
-            png.append_chunk(chunk);
+            let mut reader = BufReader::new(&file);
+            let mut png = Png::from_reader(&mut reader)
+                .map_err(|_| anyhow::format_err!("Invalid PNG file supplied"))?;
+
+            let plaintext = match (message, input_file) {
+                (Some(message), None) => message.into_bytes(),
+                (None, Some(path)) => std::fs::read(path)?,
+                (None, None) => return Err(anyhow::format_err!("either MESSAGE or --file must be supplied").into()),
+                (Some(_), Some(_)) => unreachable!("MESSAGE and --file are mutually exclusive"),
+            };
+            let data = match &passphrase {
+                Some(passphrase) => crypto::encrypt(passphrase, &plaintext),
+                None => plaintext,
+            };
 
-            let mut writer = BufWriter::new(&file);
-            writer.seek(SeekFrom::Start(0))?;
-            writer.write_all(&png.as_bytes())?;
+            let parts = fragment::split(&data, max_chunk_bytes.unwrap_or(usize::MAX))
+                .map_err(|e| anyhow::format_err!("cannot split payload: {}", e))?;
+            for part in parts {
+                png.append_chunk(Chunk::new(chunk_type.clone(), part));
+            }
+
+            rewrite_png(&file, &png)?;
         }
 
         args::Command::Remove { chunk_type } => {
-            png.remove_chunk(&chunk_type.to_string())
-                .map_err(|_| anyhow::format_err!("Cannot find chunk type {}", chunk_type))?;
+            // Stream the chunks in rather than routing through
+            // Png::from_reader, so a chunk of the target type never gets its
+            // data copied into a throwaway Vec<Chunk> just to be dropped a
+            // moment later by remove_chunk.
+            let reader = BufReader::new(&file);
+            let chunks = Png::chunks_iter_checked(reader)
+                .map_err(|_| anyhow::format_err!("Invalid PNG file supplied"))?;
+
+            let target = chunk_type.to_string();
+            let mut kept = Vec::new();
+            let mut found = false;
+            for result in chunks {
+                let candidate =
+                    result.map_err(|_| anyhow::format_err!("Invalid PNG file supplied"))?;
+                if candidate.chunk_type().to_string() == target {
+                    found = true;
+                } else {
+                    kept.push(candidate);
+                }
+            }
+            if !found {
+                return Err(anyhow::format_err!("Cannot find chunk type {}", chunk_type).into());
+            }
 
-            let mut writer = BufWriter::new(&file);
-            writer.seek(SeekFrom::Start(0))?;
-            writer.write_all(&png.as_bytes())?;
+            rewrite_png(&file, &Png::from_chunks(kept))?;
         }
 
-        args::Command::Decode { chunk_type } => {
-            let chunk = png
-                .chunk_by_type(&chunk_type.to_string())
-                .ok_or_else(|| anyhow::format_err!("Cannot find chunk type {}", chunk_type))?;
+        args::Command::Decode {
+            chunk_type,
+            out,
+            passphrase,
+        } => {
+            // Walk the chunks lazily, collecting every chunk of the target
+            // type (there may be more than one if the payload was split by
+            // --max-chunk-bytes). Unrelated chunks are read one at a time
+            // and dropped rather than kept in memory.
+            let reader = BufReader::new(&file);
+            let chunks = Png::chunks_iter_checked(reader)
+                .map_err(|_| anyhow::format_err!("Invalid PNG file supplied"))?;
 
-            let message = chunk.data_as_string()?;
-            println!("{}", message);
+            let target = chunk_type.to_string();
+            let mut matches = Vec::new();
+            for result in chunks {
+                let candidate =
+                    result.map_err(|_| anyhow::format_err!("Invalid PNG file supplied"))?;
+                if candidate.chunk_type().to_string() == target {
+                    matches.push(candidate);
+                }
+            }
+            if matches.is_empty() {
+                return Err(anyhow::format_err!("Cannot find chunk type {}", chunk_type).into());
+            }
+
+            // More than one chunk of this type doesn't necessarily mean a
+            // split payload — unrelated messages can share a chunk type too.
+            // Only fall back to the first match when the headers don't even
+            // look like a fragment set (no/inconsistent header); once they
+            // do agree on a total, a failure to reassemble (missing part,
+            // gap) is a real corruption that must be reported, not masked.
+            let data = if matches.len() > 1 {
+                let fragments = matches.iter().map(|chunk| chunk.data()).collect();
+                match fragment::reassemble(fragments) {
+                    Ok(reassembled) => reassembled,
+                    Err(fragment::FragmentError::Truncated)
+                    | Err(fragment::FragmentError::InconsistentTotal) => {
+                        matches[0].data().to_vec()
+                    }
+                    Err(e) => {
+                        return Err(anyhow::format_err!(
+                            "cannot reassemble chunk type {} from its parts: {}",
+                            chunk_type,
+                            e
+                        )
+                        .into())
+                    }
+                }
+            } else {
+                matches[0].data().to_vec()
+            };
+
+            let plaintext = match &passphrase {
+                Some(passphrase) => crypto::decrypt(passphrase, &data).map_err(|_| {
+                    anyhow::format_err!(
+                        "cannot decrypt: wrong --passphrase, or this chunk isn't encrypted"
+                    )
+                })?,
+                None if crypto::looks_encrypted(&data) => {
+                    return Err(anyhow::format_err!(
+                        "chunk data is encrypted; supply --passphrase to decode it"
+                    )
+                    .into())
+                }
+                None => data,
+            };
+
+            match out {
+                Some(path) => std::fs::write(path, &plaintext)?,
+                None => {
+                    let message = String::from_utf8(plaintext).map_err(|_| {
+                        anyhow::format_err!(
+                            "chunk data is not valid UTF-8 (it may be encrypted, or binary; try --passphrase or --out)"
+                        )
+                    })?;
+                    println!("{}", message);
+                }
+            }
         }
 
         args::Command::PrintAll => {
+            let mut reader = BufReader::new(&file);
+            let png = Png::from_reader(&mut reader)
+                .map_err(|_| anyhow::format_err!("Invalid PNG file supplied"))?;
+
+            let mut printed_types: Vec<String> = Vec::new();
             for chunk in png.chunks() {
-                let message = chunk
-                    .data_as_string()
-                    .unwrap_or_else(|_| "0001010 BinGibbrish 000".to_owned());
+                let type_name = chunk.chunk_type().to_string();
+                if printed_types.contains(&type_name) {
+                    continue;
+                }
+                printed_types.push(type_name.clone());
+
+                let matches: Vec<&Chunk> = png
+                    .chunks()
+                    .iter()
+                    .filter(|c| c.chunk_type().to_string() == type_name)
+                    .collect();
+
+                // A payload split across several same-typed chunks
+                // (--max-chunk-bytes) carries an 8-byte fragment header per
+                // part, so printing each fragment on its own would show that
+                // header mashed into the text. Reassemble before rendering,
+                // the way Decode does, and fall back to printing each chunk
+                // as stored only when reassembly fails — a lone chunk of a
+                // type is never itself a fragment (see fragment::split).
+                // Critical chunks (IHDR, PLTE, IDAT, IEND, ...) are standard
+                // PNG image data, never a pngme fragment set, so they're
+                // never candidates for reassembly even when several of the
+                // same type repeat (e.g. a typical multi-IDAT image).
+                if matches.len() > 1 && !chunk.chunk_type().is_critical() {
+                    let fragments = matches.iter().map(|c| c.data()).collect();
+                    if let Ok(reassembled) = fragment::reassemble(fragments) {
+                        println!(
+                            "chunk type: {}, length:{:>8}, crc:{:>12}| {}",
+                            type_name,
+                            reassembled.len(),
+                            "merged",
+                            render_chunk_message(&reassembled)
+                        );
+                        continue;
+                    }
+                }
+
+                for chunk in matches {
+                    println!(
+                        "chunk type: {}, length:{:>8}, crc:{:>12}| {}",
+                        chunk.chunk_type(),
+                        chunk.length(),
+                        chunk.crc(),
+                        render_chunk_message(chunk.data())
+                    );
+                }
+            }
+        }
+
+        args::Command::SetMeta { key, value } => {
+            let mut reader = BufReader::new(&file);
+            let mut png = Png::from_reader(&mut reader)
+                .map_err(|_| anyhow::format_err!("Invalid PNG file supplied"))?;
+
+            let mut fields = match png.chunk_by_type(metadata::CHUNK_TYPE) {
+                Some(chunk) => metadata::decode(chunk.data())
+                    .map_err(|e| anyhow::format_err!("existing metadata record is corrupt: {}", e))?,
+                None => Vec::new(),
+            };
+            metadata::upsert(&mut fields, key, value);
+
+            let _ = png.remove_chunk(metadata::CHUNK_TYPE);
+            png.append_chunk(Chunk::new(metadata::chunk_type(), metadata::encode(&fields)));
+
+            rewrite_png(&file, &png)?;
+        }
+
+        args::Command::GetMeta { key } => {
+            let mut reader = BufReader::new(&file);
+            let png = Png::from_reader(&mut reader)
+                .map_err(|_| anyhow::format_err!("Invalid PNG file supplied"))?;
+
+            let chunk = png
+                .chunk_by_type(metadata::CHUNK_TYPE)
+                .ok_or_else(|| anyhow::format_err!("no metadata record is present"))?;
+            let fields = metadata::decode(chunk.data())
+                .map_err(|e| anyhow::format_err!("metadata record is corrupt: {}", e))?;
+
+            let value = fields
+                .into_iter()
+                .find(|(field, _)| *field == key)
+                .map(|(_, value)| value)
+                .ok_or_else(|| anyhow::format_err!("metadata field {} is not set", key))?;
+            println!("{}", value);
+        }
+
+        args::Command::PrintMeta => {
+            let mut reader = BufReader::new(&file);
+            let png = Png::from_reader(&mut reader)
+                .map_err(|_| anyhow::format_err!("Invalid PNG file supplied"))?;
+
+            let chunk = png
+                .chunk_by_type(metadata::CHUNK_TYPE)
+                .ok_or_else(|| anyhow::format_err!("no metadata record is present"))?;
+            let fields = metadata::decode(chunk.data())
+                .map_err(|e| anyhow::format_err!("metadata record is corrupt: {}", e))?;
+
+            for (field, value) in fields {
+                println!("{}: {}", field, value);
+            }
+        }
+
+        args::Command::Verify => {
+            let reader = BufReader::new(&file);
+            let chunks = Png::chunks_iter_lenient_checked(reader)
+                .map_err(|_| anyhow::format_err!("Invalid PNG file supplied"))?;
+
+            for result in chunks {
+                let chunk = result
+                    .map_err(|_| anyhow::format_err!("PNG file is structurally corrupt"))?;
+                let status = if chunk.is_crc_valid() { "OK" } else { "MISMATCH" };
                 println!(
-                    "chunk type: {}, length:{:>8}, crc:{:>12}| {}",
+                    "chunk type: {}, stored crc: {:>12}, computed crc: {:>12} | {}",
                     chunk.chunk_type(),
-                    chunk.length(),
                     chunk.crc(),
-                    message
+                    chunk.computed_crc(),
+                    status
                 );
             }
         }
+
+        args::Command::Repair => {
+            let reader = BufReader::new(&file);
+            let chunks = Png::chunks_iter_lenient_checked(reader)
+                .map_err(|_| anyhow::format_err!("Invalid PNG file supplied"))?;
+
+            let mut repaired = Vec::new();
+            for result in chunks {
+                let chunk = result
+                    .map_err(|_| anyhow::format_err!("PNG file is structurally corrupt"))?;
+                repaired.push(Chunk::new(chunk.chunk_type().clone(), chunk.data().to_vec()));
+            }
+            let png = Png::from_chunks(repaired);
+            rewrite_png(&file, &png)?;
+        }
     }
 
     Ok(())
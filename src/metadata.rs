@@ -0,0 +1,280 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::chunk_type::ChunkType;
+
+/// The ancillary, private chunk type used to carry structured metadata
+/// records (as opposed to `Encode`'s free-form message/file payloads).
+pub const CHUNK_TYPE: &str = "meTa";
+
+/// A single structured annotation that can be stored in a metadata record.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MetaField {
+    Author,
+    Timestamp,
+    Comment,
+    MimeType,
+}
+
+impl MetaField {
+    fn tag(self) -> u8 {
+        match self {
+            MetaField::Author => 0x01,
+            MetaField::Timestamp => 0x02,
+            MetaField::Comment => 0x03,
+            MetaField::MimeType => 0x04,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Result<Self, MetadataError> {
+        match tag {
+            0x01 => Ok(MetaField::Author),
+            0x02 => Ok(MetaField::Timestamp),
+            0x03 => Ok(MetaField::Comment),
+            0x04 => Ok(MetaField::MimeType),
+            _ => Err(MetadataError::UnknownTag(tag)),
+        }
+    }
+}
+
+impl Display for MetaField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = match self {
+            MetaField::Author => "author",
+            MetaField::Timestamp => "timestamp",
+            MetaField::Comment => "comment",
+            MetaField::MimeType => "mime-type",
+        };
+        write!(f, "{}", key)
+    }
+}
+
+impl FromStr for MetaField {
+    type Err = MetadataError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "author" => Ok(MetaField::Author),
+            "timestamp" => Ok(MetaField::Timestamp),
+            "comment" => Ok(MetaField::Comment),
+            "mime-type" => Ok(MetaField::MimeType),
+            _ => Err(MetadataError::UnknownKey(s.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MetadataError {
+    UnknownKey(String),
+    UnknownTag(u8),
+    Truncated,
+    OverlongLength,
+    InvalidValue,
+}
+
+impl Display for MetadataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetadataError::UnknownKey(key) => write!(f, "{} is not a known metadata key", key),
+            MetadataError::UnknownTag(tag) => write!(f, "{} is not a known metadata tag", tag),
+            MetadataError::Truncated => write!(f, "metadata record is truncated"),
+            MetadataError::OverlongLength => write!(f, "metadata record has an overlong length prefix"),
+            MetadataError::InvalidValue => write!(f, "metadata value is not valid UTF-8"),
+        }
+    }
+}
+
+/// Encodes `fields` as a sequence of tag-length-value records: one tag byte,
+/// a DER-style length (a single byte for values under 128 bytes, or
+/// `0x80 | n` followed by `n` big-endian length bytes for larger ones), then
+/// the raw value bytes.
+pub fn encode(fields: &[(MetaField, String)]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    for (field, value) in fields {
+        bytes.push(field.tag());
+        encode_length(&mut bytes, value.len());
+        bytes.extend(value.as_bytes());
+    }
+
+    bytes
+}
+
+fn encode_length(bytes: &mut Vec<u8>, length: usize) {
+    if length < 0x80 {
+        bytes.push(length as u8);
+    } else {
+        let length_bytes = minimal_be_bytes(length);
+        bytes.push(0x80 | length_bytes.len() as u8);
+        bytes.extend(length_bytes);
+    }
+}
+
+fn minimal_be_bytes(mut length: usize) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    while length > 0 {
+        bytes.push((length & 0xff) as u8);
+        length >>= 8;
+    }
+    bytes.reverse();
+    bytes
+}
+
+/// Decodes a tag-length-value record produced by [`encode`], rejecting
+/// truncated records and length prefixes that aren't in their minimal form.
+pub fn decode(data: &[u8]) -> Result<Vec<(MetaField, String)>, MetadataError> {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+
+    while pos < data.len() {
+        let field = MetaField::from_tag(data[pos])?;
+        pos += 1;
+
+        let (length, length_size) = decode_length(&data[pos..])?;
+        pos += length_size;
+
+        let end = pos.checked_add(length).ok_or(MetadataError::OverlongLength)?;
+        let value_bytes = data.get(pos..end).ok_or(MetadataError::Truncated)?;
+        let value = String::from_utf8(value_bytes.to_vec())
+            .map_err(|_| MetadataError::InvalidValue)?;
+        pos = end;
+
+        fields.push((field, value));
+    }
+
+    Ok(fields)
+}
+
+/// Returns the decoded length and the number of bytes its prefix occupied.
+fn decode_length(data: &[u8]) -> Result<(usize, usize), MetadataError> {
+    let first = *data.first().ok_or(MetadataError::Truncated)?;
+
+    if first & 0x80 == 0 {
+        return Ok((first as usize, 1));
+    }
+
+    let extra_len = (first & 0x7f) as usize;
+    if extra_len == 0 || extra_len > std::mem::size_of::<usize>() {
+        return Err(MetadataError::OverlongLength);
+    }
+
+    let extra = data.get(1..1 + extra_len).ok_or(MetadataError::Truncated)?;
+    if extra[0] == 0 {
+        return Err(MetadataError::OverlongLength);
+    }
+
+    let length = extra.iter().fold(0usize, |acc, &b| (acc << 8) | b as usize);
+    if length < 0x80 {
+        return Err(MetadataError::OverlongLength);
+    }
+
+    Ok((length, 1 + extra_len))
+}
+
+/// Replaces `field`'s value in `fields` if present, otherwise appends it.
+pub fn upsert(fields: &mut Vec<(MetaField, String)>, field: MetaField, value: String) {
+    match fields.iter_mut().find(|(f, _)| *f == field) {
+        Some(entry) => entry.1 = value,
+        None => fields.push((field, value)),
+    }
+}
+
+pub fn chunk_type() -> ChunkType {
+    ChunkType::from_str(CHUNK_TYPE).expect("CHUNK_TYPE is a valid chunk type")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_short_value() {
+        let fields = vec![(MetaField::Author, "Ada Lovelace".to_owned())];
+        let encoded = encode(&fields);
+        assert_eq!(decode(&encoded).unwrap(), fields);
+    }
+
+    #[test]
+    fn test_roundtrip_multiple_fields() {
+        let fields = vec![
+            (MetaField::Author, "Ada Lovelace".to_owned()),
+            (MetaField::Timestamp, "2026-07-29T00:00:00Z".to_owned()),
+            (MetaField::Comment, "hidden in plain sight".to_owned()),
+            (MetaField::MimeType, "text/plain".to_owned()),
+        ];
+        let encoded = encode(&fields);
+        assert_eq!(decode(&encoded).unwrap(), fields);
+    }
+
+    #[test]
+    fn test_roundtrip_long_value() {
+        let fields = vec![(MetaField::Comment, "x".repeat(300))];
+        let encoded = encode(&fields);
+        assert_eq!(decode(&encoded).unwrap(), fields);
+    }
+
+    #[test]
+    fn test_decode_empty() {
+        assert_eq!(decode(&[]).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_decode_truncated_value() {
+        let mut encoded = encode(&[(MetaField::Author, "Ada Lovelace".to_owned())]);
+        encoded.truncate(encoded.len() - 1);
+        assert!(matches!(decode(&encoded), Err(MetadataError::Truncated)));
+    }
+
+    #[test]
+    fn test_decode_truncated_length_prefix() {
+        let encoded = encode(&[(MetaField::Comment, "x".repeat(300))]);
+        let truncated = &encoded[..2];
+        assert!(matches!(decode(truncated), Err(MetadataError::Truncated)));
+    }
+
+    #[test]
+    fn test_decode_overlong_length_prefix() {
+        // A long-form length of 5 (which fits in the single-byte short form)
+        // is not minimal and must be rejected.
+        let encoded = vec![MetaField::Author.tag(), 0x81, 5, b'h', b'e', b'l', b'l', b'o'];
+        assert!(matches!(decode(&encoded), Err(MetadataError::OverlongLength)));
+    }
+
+    #[test]
+    fn test_decode_rejects_length_that_would_overflow() {
+        let mut encoded = vec![MetaField::Author.tag(), 0x88];
+        encoded.extend([0xff; 8]);
+        assert!(matches!(decode(&encoded), Err(MetadataError::OverlongLength)));
+    }
+
+    #[test]
+    fn test_decode_unknown_tag() {
+        let encoded = vec![0xff, 0];
+        assert!(matches!(decode(&encoded), Err(MetadataError::UnknownTag(0xff))));
+    }
+
+    #[test]
+    fn test_meta_field_from_str() {
+        assert_eq!(MetaField::from_str("author").unwrap(), MetaField::Author);
+        assert!(MetaField::from_str("bogus").is_err());
+    }
+
+    #[test]
+    fn test_upsert_replaces_existing() {
+        let mut fields = vec![(MetaField::Author, "Ada".to_owned())];
+        upsert(&mut fields, MetaField::Author, "Grace".to_owned());
+        assert_eq!(fields, vec![(MetaField::Author, "Grace".to_owned())]);
+    }
+
+    #[test]
+    fn test_upsert_appends_new() {
+        let mut fields = vec![(MetaField::Author, "Ada".to_owned())];
+        upsert(&mut fields, MetaField::Comment, "hi".to_owned());
+        assert_eq!(
+            fields,
+            vec![
+                (MetaField::Author, "Ada".to_owned()),
+                (MetaField::Comment, "hi".to_owned())
+            ]
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use std::{fmt::Display, io::Read};
+
+use crate::chunk::{Chunk, ChunkReadError};
+
+#[derive(Clone)]
+pub(crate) struct Png {
+    chunks: Vec<Chunk>,
+}
+
+impl Png {
+    pub const STANDARD_HEADER: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+    pub fn from_chunks(chunks: Vec<Chunk>) -> Self {
+        Png { chunks }
+    }
+
+    pub fn append_chunk(&mut self, chunk: Chunk) {
+        match self.chunks.iter().position(|c| c.chunk_type().is_iend()) {
+            Some(iend_position) => self.chunks.insert(iend_position, chunk),
+            None => self.chunks.push(chunk),
+        }
+    }
+
+    /// Removes every chunk of `chunk_type`, not just the first, so a
+    /// payload split across several same-typed fragments is dropped in one
+    /// call rather than leaving the rest orphaned.
+    pub fn remove_chunk(&mut self, chunk_type: &str) -> Result<Vec<Chunk>, ()> {
+        let (removed, kept): (Vec<Chunk>, Vec<Chunk>) = self
+            .chunks
+            .drain(..)
+            .partition(|chunk| chunk.chunk_type().to_string() == chunk_type);
+        self.chunks = kept;
+
+        if removed.is_empty() {
+            return Err(());
+        }
+
+        Ok(removed)
+    }
+
+    pub fn header(&self) -> &[u8; 8] {
+        &Self::STANDARD_HEADER
+    }
+
+    pub fn chunks(&self) -> &[Chunk] {
+        &self.chunks
+    }
+
+    pub fn chunk_by_type(&self, chunk_type: &str) -> Option<&Chunk> {
+        self.chunks
+            .iter()
+            .find(|chunk| chunk.chunk_type().to_string() == chunk_type)
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.header().to_vec();
+        for chunk in &self.chunks {
+            bytes.extend(chunk.as_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_reader<R: Read>(reader: &mut R) -> Result<Self, ()> {
+        let chunks = Png::chunks_iter_checked(reader)?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Png::from_chunks(chunks))
+    }
+
+    /// Consumes and validates the 8-byte PNG signature off `reader`.
+    fn read_signature<R: Read>(reader: &mut R) -> Result<(), ()> {
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header).map_err(|_| ())?;
+
+        if header != Self::STANDARD_HEADER {
+            return Err(());
+        }
+
+        Ok(())
+    }
+
+    /// Walks `reader` one chunk at a time without buffering the whole file,
+    /// yielding each [`Chunk`] as it's read. The caller is responsible for
+    /// having already consumed the 8-byte PNG signature. Iteration stops
+    /// after the `IEND` chunk, or at the first read/CRC failure.
+    pub fn chunks_iter<R: Read>(reader: R) -> ChunksIter<R> {
+        ChunksIter {
+            reader,
+            lenient: false,
+            done: false,
+        }
+    }
+
+    /// Like [`Png::chunks_iter`], but tolerates chunks whose stored CRC
+    /// doesn't match their data instead of aborting, so `Verify`/`Repair`
+    /// can inspect or fix a file with corrupted (but structurally intact)
+    /// chunks.
+    pub fn chunks_iter_lenient<R: Read>(reader: R) -> ChunksIter<R> {
+        ChunksIter {
+            reader,
+            lenient: true,
+            done: false,
+        }
+    }
+
+    /// Validates the PNG signature on `reader` and hands back a lazy,
+    /// strict chunk iterator over what follows, so callers that stream
+    /// chunks (rather than buffering the whole file via [`Png::from_reader`])
+    /// don't each have to re-read and check the signature themselves.
+    pub fn chunks_iter_checked<R: Read>(mut reader: R) -> Result<ChunksIter<R>, ()> {
+        Self::read_signature(&mut reader)?;
+        Ok(Self::chunks_iter(reader))
+    }
+
+    /// Lenient counterpart to [`Png::chunks_iter_checked`], for `Verify`/
+    /// `Repair`.
+    pub fn chunks_iter_lenient_checked<R: Read>(mut reader: R) -> Result<ChunksIter<R>, ()> {
+        Self::read_signature(&mut reader)?;
+        Ok(Self::chunks_iter_lenient(reader))
+    }
+}
+
+pub(crate) struct ChunksIter<R> {
+    reader: R,
+    lenient: bool,
+    done: bool,
+}
+
+impl<R: Read> Iterator for ChunksIter<R> {
+    type Item = Result<Chunk, ()>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let result = if self.lenient {
+            Chunk::read_from_lenient(&mut self.reader)
+        } else {
+            Chunk::read_from(&mut self.reader)
+        };
+
+        match result {
+            Ok(chunk) => {
+                self.done = chunk.chunk_type().is_iend();
+                Some(Ok(chunk))
+            }
+            Err(ChunkReadError::Eof) => {
+                self.done = true;
+                None
+            }
+            Err(ChunkReadError::Invalid) => {
+                self.done = true;
+                Some(Err(()))
+            }
+        }
+    }
+}
+
+impl Display for Png {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "PNG {{")?;
+        for chunk in &self.chunks {
+            writeln!(f, "  {}", chunk.chunk_type())?;
+        }
+        write!(f, "}}")
+    }
+}